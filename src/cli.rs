@@ -15,11 +15,14 @@
  */
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
 use bollard::{API_DEFAULT_VERSION, Docker};
-use clap::{Args, ColorChoice, Parser};
+use clap::{Args, ColorChoice, Parser, ValueEnum};
+
+use crate::monitor::RuntimeConfig;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -38,16 +41,53 @@ pub struct Cli {
         env = "DHM_PROMETHEUS_ADDRESS",
     )]
     pub prometheus_address: SocketAddr,
+    #[arg(
+        long = "unhealthy-threshold",
+        value_name = "COUNT",
+        default_value_t = 0,
+        env = "DHM_UNHEALTHY_THRESHOLD"
+    )]
+    pub unhealthy_threshold: u64,
     #[arg(
         long = "restart-interval",
         value_name = "MILLISECONDS",
         env = "DHM_RESTART_INTERVAL"
     )]
     pub restart_interval: Option<u64>,
+    #[arg(
+        long = "restart-label",
+        value_name = "LABEL",
+        env = "DHM_RESTART_LABEL"
+    )]
+    pub restart_label: Option<String>,
+    #[arg(
+        long = "unhealthy-timeout",
+        value_name = "DURATION",
+        env = "DHM_UNHEALTHY_TIMEOUT",
+        value_parser = humantime::parse_duration,
+    )]
+    pub unhealthy_timeout: Option<Duration>,
+    #[arg(long = "config-file", value_name = "PATH", env = "DHM_CONFIG_FILE")]
+    pub config_file: Option<PathBuf>,
+    #[arg(
+        long = "exporter",
+        value_enum,
+        default_value = "prometheus",
+        env = "DHM_EXPORTER"
+    )]
+    pub exporter: Exporter,
+    #[arg(long = "otlp-endpoint", value_name = "URL", env = "DHM_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
     #[command(flatten, next_help_heading = "Docker connection")]
     pub connection: DockerConnection,
 }
 
+#[derive(ValueEnum, Clone, Debug)]
+pub enum Exporter {
+    Prometheus,
+    Otlp,
+}
+
 #[derive(Args, Debug)]
 #[group(required = false, multiple = false)]
 pub struct DockerConnection {
@@ -91,6 +131,52 @@ impl Cli {
     pub fn restart_interval(&self) -> Option<Duration> {
         self.restart_interval.map(Duration::from_millis)
     }
+
+    pub fn runtime_config(&self) -> RuntimeConfig {
+        RuntimeConfig {
+            restart_interval: self.restart_interval(),
+            restart_label: self.restart_label.clone(),
+            unhealthy_timeout: self.unhealthy_timeout,
+        }
+    }
+
+    /// Re-parses `args` with `KEY=VALUE` lines from the `--config-file` (if
+    /// any) spliced in as extra trailing arguments, so a reload can pick up
+    /// new values without mutating the process environment, which other
+    /// crates' internal thread pools (tonic/hyper, bollard's HTTP client)
+    /// may read concurrently via `getenv`.
+    pub fn reparse_with_config_file(args: &[String]) -> Result<Cli> {
+        let cli = Cli::try_parse_from(args)?;
+        let Some(config_file) = &cli.config_file else {
+            return Ok(cli);
+        };
+        let contents = std::fs::read_to_string(config_file)?;
+        let mut args = args.to_vec();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                if let Some(flag) = Cli::flag_for_env_key(key.trim()) {
+                    args.push(format!("--{flag}={}", value.trim()));
+                }
+            }
+        }
+        Ok(Cli::try_parse_from(&args)?)
+    }
+
+    /// Maps a `DHM_*` env variable name to the long flag that sources it, so
+    /// config-file overrides can be applied as explicit CLI arguments.
+    fn flag_for_env_key(key: &str) -> Option<&'static str> {
+        match key {
+            "DHM_UNHEALTHY_THRESHOLD" => Some("unhealthy-threshold"),
+            "DHM_RESTART_INTERVAL" => Some("restart-interval"),
+            "DHM_RESTART_LABEL" => Some("restart-label"),
+            "DHM_UNHEALTHY_TIMEOUT" => Some("unhealthy-timeout"),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -41,6 +41,13 @@ impl ContainerHealth {
         });
         values
     }
+
+    pub fn status(&self) -> String {
+        match &self.container_health_status {
+            Some(health_status) => format!("{health_status}"),
+            None => "null".to_string(),
+        }
+    }
 }
 
 impl From<Option<Health>> for ContainerHealth {
@@ -54,10 +61,6 @@ impl From<Option<Health>> for ContainerHealth {
 
 impl From<ContainerHealth> for Value {
     fn from(value: ContainerHealth) -> Self {
-        match value.container_health_status {
-            Some(health_status) => format!("{health_status}"),
-            None => "null".to_string(),
-        }
-        .into()
+        value.status().into()
     }
 }
@@ -16,6 +16,8 @@
 
 use anyhow::Result;
 use clap::Parser;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::watch;
 
 use crate::cli::Cli;
 use crate::metrics::Metrics;
@@ -31,16 +33,68 @@ pub mod monitor;
 #[tokio::main(flavor = "multi_thread", worker_threads = 1)]
 async fn main() -> Result<()> {
     logging::init()?;
-    let cli = Cli::parse();
+    let args: Vec<String> = std::env::args().collect();
+    let cli = Cli::parse_from(&args);
+    let docker = cli.connection.connect()?;
 
-    let metrics = Metrics::new()?;
+    let metrics = Metrics::new(
+        cli.exporter.clone(),
+        cli.otlp_endpoint.clone(),
+        docker.clone(),
+        cli.unhealthy_threshold,
+    )?;
     let meter = metrics.meter_provider();
-    let server = tokio::spawn(metrics.run(cli.prometheus_address));
+    let mut server = tokio::spawn(metrics.run(cli.prometheus_address));
 
-    let interval = cli.restart_interval();
-    let monitor = DockerHealthMonitor::new(interval, &meter).await?;
-    let (server, monitor) = tokio::join!(server, monitor.run());
-    monitor?;
-    server??;
+    // Honor --config-file/DHM_CONFIG_FILE on startup, not just on a later
+    // SIGHUP, so restart_interval/restart_label/unhealthy_timeout set only
+    // via config file aren't silently ignored until the first reload.
+    let initial_config = Cli::reparse_with_config_file(&args)?.runtime_config();
+    let (config_tx, config_rx) = watch::channel(initial_config);
+    let mut reload = tokio::spawn(reload_on_sighup(args, config_tx));
+
+    let monitor = DockerHealthMonitor::new(docker, config_rx, &meter).await?;
+    let mut monitor_run = std::pin::pin!(monitor.run());
+
+    // A fatal error in `server` or `monitor_run` must end the process
+    // immediately rather than wait on `reload`: unless installing the SIGHUP
+    // handler fails, it exits only on a SIGHUP received after every
+    // config_tx receiver is dropped - a state that can't occur before this
+    // loop itself returns.
+    let mut reload_done = false;
+    loop {
+        tokio::select! {
+            result = &mut server => return result?,
+            result = &mut monitor_run => return result,
+            result = &mut reload, if !reload_done => {
+                reload_done = true;
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => log::warn!("SIGHUP reload handler exited: {e}"),
+                    Err(e) => log::warn!("SIGHUP reload task panicked: {e}"),
+                }
+            }
+        }
+    }
+}
+
+async fn reload_on_sighup(
+    args: Vec<String>,
+    config_tx: watch::Sender<monitor::RuntimeConfig>,
+) -> Result<()> {
+    let mut sighup = signal(SignalKind::hangup())?;
+    loop {
+        sighup.recv().await;
+        match reload_config(&args, &config_tx) {
+            Ok(()) => log::info!("Reloaded configuration on SIGHUP"),
+            Err(e) if config_tx.is_closed() => return Err(e),
+            Err(e) => log::warn!("Failed to reload configuration: {e}"),
+        }
+    }
+}
+
+fn reload_config(args: &[String], config_tx: &watch::Sender<monitor::RuntimeConfig>) -> Result<()> {
+    let cli = Cli::reparse_with_config_file(args)?;
+    config_tx.send(cli.runtime_config())?;
     Ok(())
 }
@@ -15,9 +15,9 @@
  */
 
 use bollard::secret::ContainerSummary;
-use prometheus_client::encoding::EncodeLabelSet;
+use opentelemetry::KeyValue;
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq, EncodeLabelSet)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct ContainerSummaryLabels {
     pub id: Option<String>,
     pub image: Option<String>,
@@ -25,6 +25,27 @@ pub struct ContainerSummaryLabels {
     pub health: Option<String>,
 }
 
+impl ContainerSummaryLabels {
+    /// Renders the populated fields as OTel metric attributes.
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        [
+            self.id.as_ref().map(|id| KeyValue::new("id", id.clone())),
+            self.image
+                .as_ref()
+                .map(|image| KeyValue::new("image", image.clone())),
+            self.name
+                .as_ref()
+                .map(|name| KeyValue::new("name", name.clone())),
+            self.health
+                .as_ref()
+                .map(|health| KeyValue::new("health", health.clone())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 impl From<ContainerSummary> for ContainerSummaryLabels {
     fn from(c: ContainerSummary) -> Self {
         let name = c
@@ -16,8 +16,10 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, format_err};
+use axum::Json;
 use axum::Router;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -25,22 +27,57 @@ use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum_extra::TypedHeader;
 use axum_extra::headers::ContentType;
+use bollard::Docker;
+use bollard::query_parameters::ListContainersOptionsBuilder;
 use opentelemetry::metrics::{Meter, MeterProvider};
+use opentelemetry_otlp::MetricExporter;
 use opentelemetry_sdk::Resource;
-use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
 use prometheus::{Encoder, Registry, TextEncoder};
+use serde::Serialize;
 use tokio::net::TcpListener;
 
+use crate::cli::Exporter;
+use crate::meter_attributes::ContainerSummaryLabels;
+use crate::monitor::DockerHealthMonitor;
+
 pub const COLLECT_PERIOD_MS: u64 = 10000;
 
 pub struct Metrics {
-    registry: Registry,
     provider: SdkMeterProvider,
+    registry: Option<Registry>,
+    http: HttpState,
+}
+
+struct HttpState {
+    docker: Docker,
+    unhealthy_threshold: u64,
+}
+
+#[derive(Serialize)]
+struct ContainerHealthEntry {
+    id: String,
+    name: Option<String>,
+    health: String,
 }
 
-impl IntoResponse for &Metrics {
+#[derive(Serialize)]
+struct HealthResponse {
+    unhealthy: usize,
+    healthy: usize,
+    starting: usize,
+    none: usize,
+    containers: Vec<ContainerHealthEntry>,
+}
+
+struct AppState {
+    registry: Option<Registry>,
+    http: HttpState,
+}
+
+impl IntoResponse for &Registry {
     fn into_response(self) -> Response {
-        let metric_families = self.registry.gather();
+        let metric_families = self.gather();
         let encoder = TextEncoder::new();
         let mut result = Vec::new();
         let result = match encoder.encode(&metric_families, &mut result) {
@@ -51,31 +88,138 @@ impl IntoResponse for &Metrics {
     }
 }
 
+impl AppState {
+    async fn metrics_handler(State(state): State<Arc<AppState>>) -> Response {
+        match &state.registry {
+            Some(registry) => registry.into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    async fn health_handler(State(state): State<Arc<AppState>>) -> Response {
+        match state.http.collect_health().await {
+            Ok(response) => {
+                let status = if response.unhealthy > state.http.unhealthy_threshold as usize {
+                    StatusCode::SERVICE_UNAVAILABLE
+                } else {
+                    StatusCode::OK
+                };
+                (status, Json(response)).into_response()
+            }
+            Err(e) => {
+                log::error!("Failed to collect container health: {e}");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+impl HttpState {
+    async fn collect_health(&self) -> Result<HealthResponse> {
+        let options = ListContainersOptionsBuilder::new().all(true).build();
+        let containers = self.docker.list_containers(Some(options)).await?;
+
+        let mut response = HealthResponse {
+            unhealthy: 0,
+            healthy: 0,
+            starting: 0,
+            none: 0,
+            containers: Vec::new(),
+        };
+        for container in containers {
+            let Some(id) = container.id.clone() else {
+                continue;
+            };
+            let health = DockerHealthMonitor::health_state(&self.docker, &id).await?;
+            let health_status = health.status();
+            match health_status.as_str() {
+                "healthy" => response.healthy += 1,
+                "unhealthy" => response.unhealthy += 1,
+                "starting" => response.starting += 1,
+                _ => response.none += 1,
+            }
+            let name = ContainerSummaryLabels::from(container).name;
+            response.containers.push(ContainerHealthEntry {
+                id,
+                name,
+                health: health_status,
+            });
+        }
+        Ok(response)
+    }
+}
+
 impl Metrics {
-    async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> Response {
-        metrics.into_response()
+    fn resource() -> Resource {
+        Resource::builder()
+            .with_service_name(env!("CARGO_PKG_NAME"))
+            .build()
     }
 
-    pub fn new() -> Result<Metrics> {
-        let registry = Registry::new();
-        let exporter = opentelemetry_prometheus::exporter()
-            .with_registry(registry.clone())
-            .build()?;
-        let provider = SdkMeterProvider::builder()
-            .with_reader(exporter)
-            .with_resource(
-                Resource::builder()
-                    .with_service_name(env!("CARGO_PKG_NAME"))
-                    .build(),
-            )
-            .build();
-        Ok(Metrics { registry, provider })
+    pub fn new(
+        exporter: Exporter,
+        otlp_endpoint: Option<String>,
+        docker: Docker,
+        unhealthy_threshold: u64,
+    ) -> Result<Metrics> {
+        let http = HttpState {
+            docker,
+            unhealthy_threshold,
+        };
+        match exporter {
+            Exporter::Prometheus => {
+                let registry = Registry::new();
+                let reader = opentelemetry_prometheus::exporter()
+                    .with_registry(registry.clone())
+                    .build()?;
+                let provider = SdkMeterProvider::builder()
+                    .with_reader(reader)
+                    .with_resource(Metrics::resource())
+                    .build();
+                Ok(Metrics {
+                    provider,
+                    registry: Some(registry),
+                    http,
+                })
+            }
+            Exporter::Otlp => {
+                let otlp_endpoint = otlp_endpoint.ok_or_else(|| {
+                    format_err!("--otlp-endpoint is required when --exporter=otlp")
+                })?;
+                let exporter = MetricExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(otlp_endpoint)
+                    .build()?;
+                let reader = PeriodicReader::builder(exporter)
+                    .with_interval(Duration::from_millis(COLLECT_PERIOD_MS))
+                    .build();
+                let provider = SdkMeterProvider::builder()
+                    .with_reader(reader)
+                    .with_resource(Metrics::resource())
+                    .build();
+                Ok(Metrics {
+                    provider,
+                    registry: None,
+                    http,
+                })
+            }
+        }
     }
 
+    /// Runs the readiness/metrics HTTP server. `/health` is always served,
+    /// independent of exporter choice, so a k8s readiness probe keeps
+    /// working even when `--exporter=otlp` pushes metrics on its own
+    /// schedule; `/metrics` is only registered in Prometheus-pull mode.
     pub async fn run(self, bind_address: SocketAddr) -> Result<()> {
-        let app = Router::new()
-            .route("/metrics", get(Metrics::metrics_handler))
-            .with_state(Arc::new(self));
+        let state = Arc::new(AppState {
+            registry: self.registry,
+            http: self.http,
+        });
+        let mut app = Router::new().route("/health", get(AppState::health_handler));
+        if state.registry.is_some() {
+            app = app.route("/metrics", get(AppState::metrics_handler));
+        }
+        let app = app.with_state(state);
         let listener = TcpListener::bind(&bind_address).await?;
         axum::serve(listener, app).await?;
         Ok(())
@@ -14,80 +14,101 @@
  * limitations under the License.
  */
 
-use std::collections::HashMap;
-use std::fmt::Debug;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use anyhow::{Result, format_err};
 use bollard::Docker;
+use bollard::models::ContainerSummary;
 use bollard::query_parameters::InspectContainerOptions;
 use bollard::query_parameters::ListContainersOptionsBuilder;
 use bollard::query_parameters::RestartContainerOptions;
-use prometheus_client::collector::Collector;
-use prometheus_client::encoding::EncodeMetric;
-use prometheus_client::metrics::counter::Counter;
-use prometheus_client::metrics::family::Family;
-use prometheus_client::metrics::gauge::Gauge;
-use prometheus_client::registry::Registry;
+use opentelemetry::metrics::{Counter, Meter, ObservableGauge};
+use tokio::sync::{Mutex, watch};
 use tokio::time;
 
 use crate::container_health::ContainerHealth;
 use crate::logging::Informational;
 use crate::meter_attributes::ContainerSummaryLabels;
 
-pub struct DockerHealthMonitor {
-    docker: Docker,
-    restart_interval: Option<Duration>,
-    error_counter: Counter,
-    restart_counter: Family<ContainerSummaryLabels, Counter>,
-    failed_restart_counter: Family<ContainerSummaryLabels, Counter>,
+/// Settings that can be swapped at runtime via a SIGHUP-triggered reload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeConfig {
+    pub restart_interval: Option<Duration>,
+    pub restart_label: Option<String>,
+    pub unhealthy_timeout: Option<Duration>,
 }
 
-#[derive(Debug)]
-struct DockerHealthMonitorCollector {
+pub struct DockerHealthMonitor {
     docker: Docker,
-    error_counter: Counter,
+    config: watch::Receiver<RuntimeConfig>,
+    unhealthy_since: Mutex<HashMap<String, Instant>>,
+    error_counter: Counter<u64>,
+    restart_counter: Counter<u64>,
+    failed_restart_counter: Counter<u64>,
+    // Held only to keep the callback registered for the lifetime of the monitor.
+    _health_gauge: ObservableGauge<u64>,
 }
 
 impl DockerHealthMonitor {
     pub async fn new(
         docker: Docker,
-        restart_interval: Option<Duration>,
-        registry: &mut Registry,
+        config: watch::Receiver<RuntimeConfig>,
+        meter: &Meter,
     ) -> Result<DockerHealthMonitor> {
-        let error_counter = Counter::default();
-        registry.register("errors", "Docker client errors", error_counter.clone());
-
-        let restart_counter = Family::<ContainerSummaryLabels, Counter>::default();
-        registry.register(
-            "restarts",
-            "Number of successful restarts triggered due to a container being unhealthy",
-            restart_counter.clone(),
-        );
+        let error_counter = meter
+            .u64_counter("errors")
+            .with_description("Docker client errors")
+            .build();
 
-        let failed_restart_counter = Family::<ContainerSummaryLabels, Counter>::default();
-        registry.register(
-            "restart_failures",
-            "Number of failed restarts triggered due to a container being unhealthy",
-            failed_restart_counter.clone(),
-        );
+        let restart_counter = meter
+            .u64_counter("restarts")
+            .with_description(
+                "Number of successful restarts triggered due to a container being unhealthy",
+            )
+            .build();
 
-        let collector = DockerHealthMonitorCollector {
-            docker: docker.clone(),
-            error_counter: error_counter.clone(),
-        };
-        registry.register_collector(Box::new(collector));
+        let failed_restart_counter = meter
+            .u64_counter("restart_failures")
+            .with_description(
+                "Number of failed restarts triggered due to a container being unhealthy",
+            )
+            .build();
+
+        let health_docker = docker.clone();
+        let health_error_counter = error_counter.clone();
+        let health_gauge = meter
+            .u64_observable_gauge("health")
+            .with_description("The current state of the healthcheck")
+            .with_callback(move |observer| {
+                let result = tokio::task::block_in_place(|| {
+                    futures::executor::block_on(DockerHealthMonitor::observe_health_state(
+                        &health_docker,
+                        observer,
+                    ))
+                });
+                if let Err(e) = result {
+                    health_error_counter.add(1, &[]);
+                    log::error!("HealthCheck failed: {e}");
+                }
+            })
+            .build();
 
         Ok(DockerHealthMonitor {
             docker,
-            restart_interval,
+            config,
+            unhealthy_since: Mutex::new(HashMap::new()),
             error_counter,
             restart_counter,
             failed_restart_counter,
+            _health_gauge: health_gauge,
         })
     }
 
-    async fn health_state(docker: &Docker, container_id: &str) -> Result<ContainerHealth> {
+    pub(crate) async fn health_state(
+        docker: &Docker,
+        container_id: &str,
+    ) -> Result<ContainerHealth> {
         let container_inspect = docker
             .inspect_container(container_id, None::<InspectContainerOptions>)
             .await?;
@@ -98,13 +119,12 @@ impl DockerHealthMonitor {
         Ok(container_health_status)
     }
 
-    async fn check_health_state(
+    async fn observe_health_state(
         docker: &Docker,
-        mut encoder: prometheus_client::encoding::DescriptorEncoder<'_>,
+        observer: &dyn opentelemetry::metrics::AsyncInstrument<u64>,
     ) -> Result<()> {
         let options = ListContainersOptionsBuilder::new().all(true).build();
         let containers = docker.list_containers(Some(options)).await?;
-        let family = Family::<ContainerSummaryLabels, Gauge>::default();
         for container in containers {
             let container_id = container
                 .id
@@ -115,42 +135,51 @@ impl DockerHealthMonitor {
 
             for health_status in ContainerHealth::values() {
                 let mut labels: ContainerSummaryLabels = container.clone().into();
-                labels.health = Some(health_status.clone().into());
-                let gauge = family.get_or_create(&labels);
-                gauge.set((container_health_state == health_status).into());
+                labels.health = Some(health_status.status());
+                let value = u64::from(container_health_state == health_status);
+                observer.observe(value, &labels.attributes());
             }
         }
-        let metric_encoder = encoder.encode_descriptor(
-            "health",
-            "The current state of the healthcheck",
-            None,
-            family.metric_type(),
-        )?;
-        family.encode(metric_encoder)?;
         Ok(())
     }
 
     async fn restart_unhealthy_containers(&self) -> Result<()> {
+        let config = self.config.borrow().clone();
+
         let mut filters = HashMap::new();
         filters.insert("health", vec!["unhealthy"]);
+        if let Some(restart_label) = &config.restart_label {
+            filters.insert("label", vec![restart_label.as_str()]);
+        }
         let options = ListContainersOptionsBuilder::new()
             .all(true)
             .filters(&filters)
             .build();
         let unhealthy_containers = self.docker.list_containers(Some(options)).await?;
+
+        let due_for_restart = self
+            .debounce(&unhealthy_containers, config.unhealthy_timeout)
+            .await;
+
         for container in unhealthy_containers {
+            if let Some(id) = &container.id {
+                if !due_for_restart.contains(id) {
+                    continue;
+                }
+            }
             let container_info = container.info();
             log::info!("Restarting unhealthy container: {container_info}");
             if let Some(id) = &container.id {
                 self.docker
                     .restart_container(id, None::<RestartContainerOptions>)
                     .await?;
-                self.restart_counter.get_or_create(&container.into()).inc();
+                self.unhealthy_since.lock().await.remove(id);
+                let labels: ContainerSummaryLabels = container.into();
+                self.restart_counter.add(1, &labels.attributes());
                 log::info!("Restarted unhealthy container: {container_info}");
             } else {
-                self.failed_restart_counter
-                    .get_or_create(&container.into())
-                    .inc();
+                let labels: ContainerSummaryLabels = container.into();
+                self.failed_restart_counter.add(1, &labels.attributes());
                 log::warn!(
                     "Failed to restart unhealthy container due to missing ID: {container_info}"
                 );
@@ -159,36 +188,184 @@ impl DockerHealthMonitor {
         Ok(())
     }
 
+    async fn debounce(
+        &self,
+        unhealthy_containers: &[ContainerSummary],
+        unhealthy_timeout: Option<Duration>,
+    ) -> HashSet<String> {
+        let ids = unhealthy_containers
+            .iter()
+            .filter_map(|container| container.id.clone());
+        let mut unhealthy_since = self.unhealthy_since.lock().await;
+        DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            ids,
+            unhealthy_timeout,
+            Instant::now(),
+        )
+    }
+
+    /// Pure bookkeeping behind [`DockerHealthMonitor::debounce`]: tracks, per
+    /// container id, when it was first observed unhealthy and reports the
+    /// ids that have been unhealthy for at least `unhealthy_timeout`.
+    /// Recovered containers (absent from `currently_unhealthy_ids`) have
+    /// their clock cleared, so a later relapse starts counting from `now`.
+    fn debounce_ids(
+        unhealthy_since: &mut HashMap<String, Instant>,
+        currently_unhealthy_ids: impl Iterator<Item = String>,
+        unhealthy_timeout: Option<Duration>,
+        now: Instant,
+    ) -> HashSet<String> {
+        let Some(unhealthy_timeout) = unhealthy_timeout else {
+            return currently_unhealthy_ids.collect();
+        };
+
+        let currently_unhealthy: HashSet<String> = currently_unhealthy_ids.collect();
+        unhealthy_since.retain(|id, _| currently_unhealthy.contains(id));
+
+        let mut due = HashSet::new();
+        for id in currently_unhealthy {
+            let first_seen = *unhealthy_since.entry(id.clone()).or_insert(now);
+            if now.duration_since(first_seen) >= unhealthy_timeout {
+                due.insert(id);
+            }
+        }
+        due
+    }
+
     pub async fn run(&self) -> Result<()> {
-        let interval = self.restart_interval.map(time::interval);
-        if let Some(mut interval) = interval {
+        let mut config = self.config.clone();
+        'reload: loop {
+            let restart_interval = config.borrow().restart_interval;
+            let Some(restart_interval) = restart_interval else {
+                if config.changed().await.is_err() {
+                    return Ok(());
+                }
+                continue 'reload;
+            };
+            let mut interval = time::interval(restart_interval);
             loop {
-                interval.tick().await;
-                if let Err(e) = self.restart_unhealthy_containers().await {
-                    self.error_counter.inc();
-                    log::warn!("Failed to restart: {e}")
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = self.restart_unhealthy_containers().await {
+                            self.error_counter.add(1, &[]);
+                            log::warn!("Failed to restart: {e}")
+                        }
+                    }
+                    changed = config.changed() => {
+                        if changed.is_err() {
+                            return Ok(());
+                        }
+                        if config.borrow().restart_interval != Some(restart_interval) {
+                            continue 'reload;
+                        }
+                    }
                 }
             }
         }
-        Ok(())
     }
 }
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
 
-impl Collector for DockerHealthMonitorCollector {
-    fn encode(
-        &self,
-        encoder: prometheus_client::encoding::DescriptorEncoder,
-    ) -> std::result::Result<(), std::fmt::Error> {
-        tokio::task::block_in_place(|| {
-            futures::executor::block_on(DockerHealthMonitor::check_health_state(
-                &self.docker,
-                encoder,
-            ))
-        })
-        .map_err(|e| {
-            self.error_counter.inc();
-            log::error!("HealthCheck failed: {e}");
-            std::fmt::Error
-        })
+    use crate::monitor::DockerHealthMonitor;
+
+    #[test]
+    fn not_due_before_timeout_elapses() {
+        let mut unhealthy_since = HashMap::new();
+        let now = Instant::now();
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            now,
+        );
+        assert!(due.is_empty());
+
+        let still_early = now + Duration::from_secs(5);
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            still_early,
+        );
+        assert!(due.is_empty());
+    }
+
+    #[test]
+    fn due_once_timeout_elapses() {
+        let mut unhealthy_since = HashMap::new();
+        let now = Instant::now();
+        DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            now,
+        );
+
+        let after_timeout = now + Duration::from_secs(10);
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            after_timeout,
+        );
+        assert_eq!(due, ["a".to_string()].into_iter().collect());
+    }
+
+    #[test]
+    fn recovery_then_relapse_resets_the_clock() {
+        let mut unhealthy_since = HashMap::new();
+        let now = Instant::now();
+        DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            now,
+        );
+
+        // Container recovers: it's no longer in the currently-unhealthy set,
+        // so its first-seen clock is cleared.
+        DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            std::iter::empty(),
+            Some(Duration::from_secs(10)),
+            now + Duration::from_secs(5),
+        );
+        assert!(unhealthy_since.is_empty());
+
+        // Container relapses later: even though the original timeout window
+        // has long since passed, it isn't due immediately because the clock
+        // restarted on relapse.
+        let relapsed_at = now + Duration::from_secs(20);
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            relapsed_at,
+        );
+        assert!(due.is_empty());
+
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            Some(Duration::from_secs(10)),
+            relapsed_at + Duration::from_secs(10),
+        );
+        assert_eq!(due, ["a".to_string()].into_iter().collect());
+    }
+
+    #[test]
+    fn due_immediately_when_no_timeout_configured() {
+        let mut unhealthy_since = HashMap::new();
+        let due = DockerHealthMonitor::debounce_ids(
+            &mut unhealthy_since,
+            vec!["a".to_string()].into_iter(),
+            None,
+            Instant::now(),
+        );
+        assert_eq!(due, ["a".to_string()].into_iter().collect());
     }
 }